@@ -0,0 +1,125 @@
+use bitcoin::Network;
+use dirs_next::data_dir;
+use serde::{Deserialize, Serialize};
+use std::{fs, path::PathBuf};
+
+/// Per-network mempool/esplora API base URL overrides. Absent entries fall
+/// back to the public mempool.space instance for that network.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ApiUrls {
+    pub testnet: Option<String>,
+    pub bitcoin: Option<String>,
+    pub signet: Option<String>,
+}
+
+/// Default price oracle endpoint, used unless overridden by `price_api_url`.
+/// Returns the current BTC spot price in major fiat currencies, keyed by ISO
+/// 4217 currency code.
+const DEFAULT_PRICE_API_URL: &str = "https://mempool.space/api/v1/prices";
+
+/// Wallet configuration persisted as TOML in the app data dir, loaded once at
+/// startup and overridable per-invocation by CLI flags (e.g. `--network`).
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Config {
+    /// Default network to operate on: `testnet`, `bitcoin`, or `signet`.
+    pub network: String,
+    #[serde(default)]
+    pub api_urls: ApiUrls,
+    /// Default fee tier for `send`/`build-psbt` when `--fee` isn't given.
+    pub default_fee: String,
+    /// Price oracle endpoint, overriding `DEFAULT_PRICE_API_URL`.
+    #[serde(default)]
+    pub price_api_url: Option<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            network: "testnet".to_string(),
+            api_urls: ApiUrls::default(),
+            default_fee: "medium".to_string(),
+            price_api_url: None,
+        }
+    }
+}
+
+impl Config {
+    fn path() -> PathBuf {
+        let mut path = data_dir().expect("Could not find data directory");
+        path.push("bitcli");
+        fs::create_dir_all(&path).expect("Failed to create app data directory");
+        path.push("config.toml");
+        path
+    }
+
+    /// Loads the config file, writing out a default one (and letting the user
+    /// know) the first time it's missing.
+    pub fn load() -> Self {
+        let path = Self::path();
+        if !path.exists() {
+            println!(
+                "Config not initialized; writing default config to {}",
+                path.display()
+            );
+            let config = Self::default();
+            config.save();
+            return config;
+        }
+
+        let contents = fs::read_to_string(&path).expect("Failed to read config file");
+        toml::from_str(&contents).expect("Failed to parse config file")
+    }
+
+    fn save(&self) {
+        let contents = toml::to_string_pretty(self).expect("Failed to serialize config");
+        fs::write(Self::path(), contents).expect("Failed to write config file");
+    }
+
+    /// Parses `self.network` into a `Network`, falling back to testnet for an
+    /// unrecognized value.
+    pub fn network(&self) -> Network {
+        parse_network(&self.network)
+    }
+
+    /// Resolves the API base URL for `network`, preferring a configured
+    /// override and falling back to the public mempool.space instance.
+    pub fn api_url(&self, network: Network) -> String {
+        let override_url = match network {
+            Network::Testnet => self.api_urls.testnet.clone(),
+            Network::Bitcoin => self.api_urls.bitcoin.clone(),
+            Network::Signet => self.api_urls.signet.clone(),
+            _ => None,
+        };
+
+        override_url.unwrap_or_else(|| default_api_url(network))
+    }
+
+    /// Resolves the price oracle endpoint, preferring a configured override
+    /// and falling back to the public mempool.space instance.
+    pub fn price_api_url(&self) -> String {
+        self.price_api_url
+            .clone()
+            .unwrap_or_else(|| DEFAULT_PRICE_API_URL.to_string())
+    }
+}
+
+/// Parses a config/CLI network string, defaulting to testnet for anything
+/// unrecognized.
+pub fn parse_network(raw: &str) -> Network {
+    match raw {
+        "bitcoin" => Network::Bitcoin,
+        "signet" => Network::Signet,
+        _ => Network::Testnet,
+    }
+}
+
+/// The public mempool.space API base URL for `network`, or empty for
+/// networks we don't have a known instance for.
+pub fn default_api_url(network: Network) -> String {
+    match network {
+        Network::Testnet => "https://mempool.space/testnet4".to_string(),
+        Network::Bitcoin => "https://mempool.space".to_string(),
+        Network::Signet => "https://mempool.space/signet".to_string(),
+        _ => String::new(),
+    }
+}
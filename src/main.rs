@@ -1,18 +1,70 @@
-use std::time::Duration;
+use std::{str::FromStr, time::Duration};
 
-use bitcoin::Network;
-use clap::{Parser, Subcommand};
+use bitcoin::{Address, Network};
+use clap::{Parser, Subcommand, ValueEnum};
 use dotenv::dotenv;
 use indicatif::{ProgressBar, ProgressStyle};
+use rpassword::prompt_password;
+use rust_decimal::Decimal;
 
+mod config;
 mod wallet;
 
+/// Fee tier to select for a `Send`, mapped onto `wallet::FeeTier`.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum FeeTier {
+    Low,
+    Medium,
+    High,
+}
+
+impl From<FeeTier> for wallet::FeeTier {
+    fn from(tier: FeeTier) -> Self {
+        match tier {
+            FeeTier::Low => wallet::FeeTier::Low,
+            FeeTier::Medium => wallet::FeeTier::Medium,
+            FeeTier::High => wallet::FeeTier::High,
+        }
+    }
+}
+
+/// Parses a config/CLI fee tier string, defaulting to medium for anything
+/// unrecognized.
+fn parse_fee_tier(raw: &str) -> FeeTier {
+    match raw {
+        "low" => FeeTier::Low,
+        "high" => FeeTier::High,
+        _ => FeeTier::Medium,
+    }
+}
+
+/// Network to operate on, overriding the config file's `network` setting.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum CliNetwork {
+    Testnet,
+    Bitcoin,
+    Signet,
+}
+
+impl From<CliNetwork> for Network {
+    fn from(network: CliNetwork) -> Self {
+        match network {
+            CliNetwork::Testnet => Network::Testnet,
+            CliNetwork::Bitcoin => Network::Bitcoin,
+            CliNetwork::Signet => Network::Signet,
+        }
+    }
+}
+
 /// Cli bitcoin wallet
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+    /// Network to operate on, overriding the config file
+    #[arg(long, global = true, value_enum)]
+    network: Option<CliNetwork>,
 }
 
 #[derive(Subcommand)]
@@ -20,13 +72,17 @@ enum Commands {
     /// Create a wallet
     #[command(alias = "c")]
     Create,
-    /// Send bitcoin to an address
+    /// Send bitcoin to one or more recipients in a single transaction
     #[command(alias = "s")]
     Send {
-        /// The address to send the bitcoin to
-        to: String,
-        /// The amount of bitcoin to send
-        amount: u64,
+        /// Recipients as `address:amount` pairs (amount in satoshis, or e.g. `50usd` for fiat)
+        recipients: Vec<String>,
+        /// Fee tier to use for the transaction, overriding the config default
+        #[arg(long, value_enum)]
+        fee: Option<FeeTier>,
+        /// Explicit fee rate in sat/vB, overriding --fee
+        #[arg(long)]
+        sat_per_vb: Option<u64>,
     },
     /// Create a wallet from a mnemonic phrase
     #[command(alias = "m")]
@@ -36,7 +92,11 @@ enum Commands {
     },
     /// Get the balance of the wallet
     #[command(alias = "b")]
-    Balance,
+    Balance {
+        /// Also show the balance's approximate fiat (USD) value
+        #[arg(long)]
+        fiat: bool,
+    },
     /// Get the address of the wallet
     #[command(alias = "a")]
     Address,
@@ -46,19 +106,148 @@ enum Commands {
     /// Reset the wallet
     #[command(alias = "r")]
     Reset,
+    /// Show transaction history
+    #[command(alias = "hist")]
+    History {
+        /// Print machine-readable JSON instead of a table
+        #[arg(long)]
+        json: bool,
+    },
+    /// Build an unsigned PSBT instead of broadcasting, for offline or multisig signing
+    BuildPsbt {
+        /// Recipients as `address:amount` pairs (amount in satoshis, or e.g. `50usd` for fiat)
+        recipients: Vec<String>,
+        /// Fee tier to use for the transaction, overriding the config default
+        #[arg(long, value_enum)]
+        fee: Option<FeeTier>,
+        /// Explicit fee rate in sat/vB, overriding --fee
+        #[arg(long)]
+        sat_per_vb: Option<u64>,
+    },
+    /// Sign a base64-encoded PSBT with this wallet's keys
+    SignPsbt {
+        /// The base64-encoded PSBT
+        psbt: String,
+    },
+    /// Finalize a fully-signed base64-encoded PSBT and broadcast it
+    FinalizePsbt {
+        /// The base64-encoded PSBT
+        psbt: String,
+    },
+}
+
+/// A recipient amount, either already in satoshis or denominated in fiat
+/// (e.g. `50usd`) and yet to be resolved against the current exchange rate.
+enum RecipientAmount {
+    Sats(u64),
+    Usd(Decimal),
+}
+
+/// Parses `address:amount` pairs from the `Send`/`BuildPsbt` commands into
+/// `(address, amount)` tuples, validating each address is well-formed and
+/// valid for `network`. `amount` is satoshis unless it carries a `usd`
+/// suffix, e.g. `50usd`.
+fn parse_recipients(
+    raw: &[String],
+    network: Network,
+) -> Result<Vec<(String, RecipientAmount)>, String> {
+    if raw.is_empty() {
+        return Err("At least one recipient is required".to_string());
+    }
+
+    raw.iter()
+        .map(|pair| {
+            let (address, amount) = pair
+                .split_once(':')
+                .ok_or_else(|| format!("Invalid recipient `{}`, expected address:amount", pair))?;
+
+            Address::from_str(address)
+                .map_err(|e| format!("Invalid address `{}`: {}", address, e))?
+                .require_network(network)
+                .map_err(|e| format!("Invalid address `{}`: {}", address, e))?;
+
+            let amount = match amount.strip_suffix("usd") {
+                Some(usd) => {
+                    let usd: Decimal = usd
+                        .parse()
+                        .map_err(|_| format!("Invalid fiat amount in `{}`", pair))?;
+                    RecipientAmount::Usd(usd)
+                }
+                None => {
+                    let sats: u64 = amount
+                        .parse()
+                        .map_err(|_| format!("Invalid amount in `{}`", pair))?;
+                    RecipientAmount::Sats(sats)
+                }
+            };
+
+            Ok((address.to_string(), amount))
+        })
+        .collect()
+}
+
+/// Resolves any fiat-denominated recipient amounts to satoshis, fetching the
+/// current BTC/USD price only if at least one recipient needs it.
+async fn resolve_recipients(
+    wallet: &wallet::Wallet,
+    recipients: Vec<(String, RecipientAmount)>,
+) -> Result<Vec<(String, u64)>, String> {
+    let needs_price = recipients
+        .iter()
+        .any(|(_, amount)| matches!(amount, RecipientAmount::Usd(_)));
+
+    let price = if needs_price {
+        Some(
+            wallet
+                .get_btc_price()
+                .await
+                .map_err(|e| format!("Failed to fetch BTC/USD price: {}", e))?,
+        )
+    } else {
+        None
+    };
+
+    recipients
+        .into_iter()
+        .map(|(address, amount)| {
+            let sats = match amount {
+                RecipientAmount::Sats(sats) => sats,
+                RecipientAmount::Usd(usd) => {
+                    wallet::Wallet::fiat_to_sats(usd, price.expect("price fetched above"))
+                        .ok_or_else(|| format!("Could not convert {}usd to sats", usd))?
+                }
+            };
+            Ok((address, sats))
+        })
+        .collect()
 }
 
 #[tokio::main]
 async fn main() {
     dotenv().ok();
     let args = Cli::parse();
+    let config = config::Config::load();
+
+    let network: Network = args
+        .network
+        .map(Network::from)
+        .unwrap_or_else(|| config.network());
+    let default_fee = parse_fee_tier(&config.default_fee);
+
+    // Reset doesn't need the decrypted mnemonic, so it must stay reachable
+    // even if the wallet can't be unlocked (e.g. a forgotten passphrase).
+    if matches!(args.command, Commands::Reset) {
+        wallet::Wallet::reset();
+        return;
+    }
 
     let existing_mnemonic = wallet::Wallet::load_mnemonic();
 
     let wallet = if !existing_mnemonic.is_empty() {
         Some(wallet::Wallet::from_mnemonic(
+            &config,
             &existing_mnemonic,
-            Network::Testnet,
+            network,
         ))
     } else {
         None
@@ -75,38 +264,70 @@ async fn main() {
 
     match args.command {
         Commands::Create => {
-            let wallet = wallet::Wallet::create(Network::Testnet);
+            let passphrase =
+                prompt_password("New passphrase: ").expect("Failed to read passphrase");
+            let wallet = wallet::Wallet::create(&config, network, &passphrase);
             println!("{:?}", wallet);
         }
-        Commands::Send { to, amount } => match wallet {
-            Some(wallet) => {
-                spinner.set_message("Sending transaction...");
-                match wallet.send(&to, amount).await {
-                    Ok(txid) => println!("Transaction submitted successfully: {}", txid),
-                    Err(e) => println!("Error submitting transaction: {}", e),
-                }
-            }
+        Commands::Send {
+            recipients,
+            fee,
+            sat_per_vb,
+        } => match wallet {
+            Some(wallet) => match parse_recipients(&recipients, network) {
+                Ok(recipients) => match resolve_recipients(&wallet, recipients).await {
+                    Ok(recipients) => {
+                        spinner.set_message("Sending transaction...");
+                        let fee = fee.unwrap_or(default_fee).into();
+                        match wallet.send(&recipients, fee, sat_per_vb).await {
+                            Ok(txid) => println!("Transaction submitted successfully: {}", txid),
+                            Err(e) => println!("Error submitting transaction: {}", e),
+                        }
+                    }
+                    Err(e) => println!("Error resolving recipient amounts: {}", e),
+                },
+                Err(e) => println!("Error parsing recipients: {}", e),
+            },
             None => println!("Wallet not initialized"),
         },
         Commands::Mnemonic { mnemonic } => {
-            let wallet =
-                wallet::Wallet::from_mnemonic(mnemonic.join(" ").as_str(), Network::Testnet);
+            let phrase = mnemonic.join(" ");
+            let passphrase =
+                prompt_password("New passphrase: ").expect("Failed to read passphrase");
+            let wallet = wallet::Wallet::from_mnemonic(&config, &phrase, network);
+            wallet::Wallet::save_mnemonic(&phrase, &passphrase);
             println!("{:?}", wallet);
         }
-        Commands::Balance => match wallet {
+        Commands::Balance { fiat } => match wallet {
             Some(wallet) => {
                 spinner.set_message("Fetching balance...");
-                spinner.finish_with_message(format!(
-                    "Balance: {}",
-                    wallet.get_balance().await.unwrap()
-                ));
+                match wallet.get_balance().await {
+                    Ok(balance) => {
+                        let mut message = format!("Balance: {}", balance);
+                        if fiat {
+                            match wallet.get_btc_price().await {
+                                Ok(price) => message.push_str(&format!(
+                                    " (≈ ${:.2} USD)",
+                                    wallet::Wallet::sats_to_fiat(balance, price)
+                                )),
+                                Err(e) => message
+                                    .push_str(&format!(" (failed to fetch USD price: {})", e)),
+                            }
+                        }
+                        spinner.finish_with_message(message);
+                    }
+                    Err(e) => spinner.finish_with_message(format!("Error fetching balance: {}", e)),
+                }
             }
             None => println!("Wallet not initialized"),
         },
         Commands::Address => match wallet {
             Some(wallet) => {
                 spinner.set_message("Fetching address...");
-                spinner.finish_with_message(format!("Address: {}", wallet.get_address()));
+                match wallet.get_address().await {
+                    Ok(address) => spinner.finish_with_message(format!("Address: {}", address)),
+                    Err(e) => spinner.finish_with_message(format!("Error fetching address: {}", e)),
+                }
             }
             None => println!("Wallet not initialized"),
         },
@@ -114,13 +335,96 @@ async fn main() {
             Some(wallet) => spinner.finish_with_message(wallet.get_network()),
             None => println!("Wallet not initialized"),
         },
-        Commands::Reset => match wallet {
-            Some(wallet) => wallet.reset(),
+        Commands::Reset => unreachable!("handled above, before the wallet is unlocked"),
+        Commands::History { json } => match wallet {
+            Some(wallet) => {
+                spinner.set_message("Fetching history...");
+                match wallet.get_history().await {
+                    Ok(history) => {
+                        spinner.finish_and_clear();
+                        print_history(&history, json);
+                    }
+                    Err(e) => spinner.finish_with_message(format!("Error fetching history: {}", e)),
+                }
+            }
             None => println!("Wallet not initialized"),
         },
-        // Commands::History => match wallet {
-        //     Some(wallet) => println!("History: {:?}", wallet.get_history()),
-        //     None => println!("Wallet not initialized"),
-        // },
+        Commands::BuildPsbt {
+            recipients,
+            fee,
+            sat_per_vb,
+        } => match wallet {
+            Some(wallet) => match parse_recipients(&recipients, network) {
+                Ok(recipients) => match resolve_recipients(&wallet, recipients).await {
+                    Ok(recipients) => {
+                        spinner.set_message("Building PSBT...");
+                        let fee = fee.unwrap_or(default_fee).into();
+                        match wallet.build_psbt(&recipients, fee, sat_per_vb).await {
+                            Ok(psbt) => spinner.finish_with_message(format!("PSBT: {}", psbt)),
+                            Err(e) => {
+                                spinner.finish_with_message(format!("Error building PSBT: {}", e))
+                            }
+                        }
+                    }
+                    Err(e) => println!("Error resolving recipient amounts: {}", e),
+                },
+                Err(e) => println!("Error parsing recipients: {}", e),
+            },
+            None => println!("Wallet not initialized"),
+        },
+        Commands::SignPsbt { psbt } => match wallet {
+            Some(wallet) => match wallet.sign_psbt(&psbt) {
+                Ok(psbt) => println!("Signed PSBT: {}", psbt),
+                Err(e) => println!("Error signing PSBT: {}", e),
+            },
+            None => println!("Wallet not initialized"),
+        },
+        Commands::FinalizePsbt { psbt } => match wallet {
+            Some(wallet) => {
+                spinner.set_message("Finalizing and broadcasting PSBT...");
+                match wallet.finalize_psbt(&psbt).await {
+                    Ok(txid) => spinner.finish_with_message(format!(
+                        "Transaction submitted successfully: {}",
+                        txid
+                    )),
+                    Err(e) => spinner.finish_with_message(format!("Error finalizing PSBT: {}", e)),
+                }
+            }
+            None => println!("Wallet not initialized"),
+        },
+    }
+}
+
+fn print_history(history: &[wallet::HistoryEntry], json: bool) {
+    if json {
+        println!("{}", serde_json::to_string_pretty(history).unwrap());
+        return;
+    }
+
+    if history.is_empty() {
+        println!("No transactions found");
+        return;
+    }
+
+    println!(
+        "{:<66}  {:<11}  {:>10}  {:>14}  {:>10}",
+        "TXID", "STATUS", "HEIGHT", "NET (sats)", "FEE"
+    );
+    for entry in history {
+        println!(
+            "{:<66}  {:<11}  {:>10}  {:>14}  {:>10}",
+            entry.txid,
+            if entry.confirmed {
+                "confirmed"
+            } else {
+                "unconfirmed"
+            },
+            entry
+                .block_height
+                .map(|h| h.to_string())
+                .unwrap_or_else(|| "-".to_string()),
+            entry.net_sats,
+            entry.fee
+        );
     }
 }
@@ -0,0 +1,221 @@
+use super::Utxo;
+
+/// Upper bound on Branch-and-Bound search steps before giving up and falling
+/// back to largest-first selection.
+const BNB_MAX_ITERATIONS: usize = 100_000;
+
+/// Selects a subset of `utxos` covering `target`, using Bitcoin Core's
+/// Branch-and-Bound algorithm: depth-first search over "include then
+/// exclude" at each UTXO (sorted largest-first), looking for a subset whose
+/// total (net of `fee_per_input` per selected input) lands in
+/// `[target, target + cost_of_change]` so no change output is needed.
+///
+/// Falls back to a largest-first selection that does produce change if BnB
+/// finds no match within `BNB_MAX_ITERATIONS` steps.
+pub fn select_coins<'a>(
+    utxos: &'a [Utxo],
+    target: u64,
+    fee_per_input: u64,
+    cost_of_change: u64,
+) -> Option<Vec<&'a Utxo>> {
+    branch_and_bound(utxos, target, fee_per_input, cost_of_change)
+        .or_else(|| largest_first(utxos, target, fee_per_input))
+}
+
+fn branch_and_bound<'a>(
+    utxos: &'a [Utxo],
+    target: u64,
+    fee_per_input: u64,
+    cost_of_change: u64,
+) -> Option<Vec<&'a Utxo>> {
+    let mut sorted: Vec<&Utxo> = utxos.iter().collect();
+    sorted.sort_by(|a, b| b.value.cmp(&a.value));
+
+    let effective_values: Vec<u64> = sorted
+        .iter()
+        .map(|utxo| utxo.value.saturating_sub(fee_per_input))
+        .collect();
+
+    let mut best: Option<Vec<&Utxo>> = None;
+    let mut best_waste = u64::MAX;
+    let mut current: Vec<&Utxo> = Vec::new();
+    let mut steps = 0usize;
+
+    search(
+        &sorted,
+        &effective_values,
+        0,
+        0,
+        &mut current,
+        target,
+        cost_of_change,
+        &mut steps,
+        &mut best,
+        &mut best_waste,
+    );
+
+    best
+}
+
+#[allow(clippy::too_many_arguments)]
+fn search<'a>(
+    sorted: &[&'a Utxo],
+    effective_values: &[u64],
+    index: usize,
+    current_value: u64,
+    current: &mut Vec<&'a Utxo>,
+    target: u64,
+    cost_of_change: u64,
+    steps: &mut usize,
+    best: &mut Option<Vec<&'a Utxo>>,
+    best_waste: &mut u64,
+) {
+    *steps += 1;
+    if *steps > BNB_MAX_ITERATIONS {
+        return;
+    }
+
+    if current_value >= target {
+        let waste = current_value - target;
+        if waste <= cost_of_change && waste < *best_waste {
+            *best_waste = waste;
+            *best = Some(current.clone());
+        }
+        // Adding more UTXOs only increases the total, so this branch can't improve.
+        return;
+    }
+
+    if index >= sorted.len() {
+        return;
+    }
+
+    // Include sorted[index].
+    current.push(sorted[index]);
+    search(
+        sorted,
+        effective_values,
+        index + 1,
+        current_value + effective_values[index],
+        current,
+        target,
+        cost_of_change,
+        steps,
+        best,
+        best_waste,
+    );
+    current.pop();
+
+    // Exclude sorted[index].
+    search(
+        sorted,
+        effective_values,
+        index + 1,
+        current_value,
+        current,
+        target,
+        cost_of_change,
+        steps,
+        best,
+        best_waste,
+    );
+}
+
+/// Greedily selects the largest UTXOs first until `target` (net of
+/// `fee_per_input` per selected input) is covered. Unlike `branch_and_bound`,
+/// this is expected to leave change.
+fn largest_first<'a>(utxos: &'a [Utxo], target: u64, fee_per_input: u64) -> Option<Vec<&'a Utxo>> {
+    let mut sorted: Vec<&Utxo> = utxos.iter().collect();
+    sorted.sort_by(|a, b| b.value.cmp(&a.value));
+
+    let mut selected = Vec::new();
+    let mut total = 0u64;
+
+    for utxo in sorted {
+        selected.push(utxo);
+        total += utxo.value.saturating_sub(fee_per_input);
+        if total >= target {
+            return Some(selected);
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::UtxoStatus;
+    use super::*;
+
+    fn utxo(value: u64) -> Utxo {
+        Utxo {
+            txid: "0".repeat(64),
+            vout: 0,
+            value,
+            status: UtxoStatus {
+                confirmed: true,
+                block_height: Some(1),
+                block_hash: None,
+                block_time: None,
+            },
+            chain: 0,
+            index: 0,
+        }
+    }
+
+    #[test]
+    fn branch_and_bound_finds_an_exact_no_change_match() {
+        let utxos = vec![utxo(100_110), utxo(500_000)];
+        let fee_per_input = 10;
+        let cost_of_change = 100;
+
+        // 100_110 net of one input's fee is 100_100, a surplus of 100 over
+        // the 100_000 target, within cost_of_change.
+        let selected = branch_and_bound(&utxos, 100_000, fee_per_input, cost_of_change).unwrap();
+
+        assert_eq!(selected.len(), 1);
+        assert_eq!(selected[0].value, 100_110);
+    }
+
+    #[test]
+    fn branch_and_bound_returns_none_without_a_tight_match() {
+        let utxos = vec![utxo(50_000)];
+
+        // No subset lands within [target, target + cost_of_change].
+        assert!(branch_and_bound(&utxos, 10_000, 10, 99).is_none());
+    }
+
+    #[test]
+    fn select_coins_falls_back_to_largest_first() {
+        let utxos = vec![utxo(50_000)];
+
+        // No tight BnB match for a 10,000-sat target against a 50,000-sat
+        // UTXO, so select_coins must fall back to largest_first rather than
+        // returning None.
+        let selected = select_coins(&utxos, 10_000, 10, 99).unwrap();
+
+        assert_eq!(selected.len(), 1);
+        assert_eq!(selected[0].value, 50_000);
+    }
+
+    #[test]
+    fn largest_first_selects_fewest_utxos_covering_target() {
+        let utxos = vec![utxo(100), utxo(50_000), utxo(200)];
+
+        let selected = largest_first(&utxos, 10_000, 0).unwrap();
+
+        assert_eq!(selected.len(), 1);
+        assert_eq!(selected[0].value, 50_000);
+    }
+
+    #[test]
+    fn select_coins_returns_none_for_an_empty_utxo_set() {
+        assert!(select_coins(&[], 10_000, 10, 99).is_none());
+    }
+
+    #[test]
+    fn select_coins_returns_none_when_funds_are_insufficient() {
+        let utxos = vec![utxo(1_000), utxo(2_000)];
+
+        assert!(select_coins(&utxos, 10_000, 10, 99).is_none());
+    }
+}
@@ -0,0 +1,1035 @@
+use crate::config::Config;
+use argon2::Argon2;
+use base64::{engine::general_purpose::STANDARD as base64_standard, Engine};
+use bip39::{Language, Mnemonic};
+use bitcoin::{
+    absolute::LockTime,
+    bip32::{ChildNumber, DerivationPath, Xpriv},
+    consensus::serialize,
+    ecdsa,
+    key::Secp256k1,
+    psbt::Psbt,
+    secp256k1::{Message, SecretKey},
+    sighash::SighashCache,
+    transaction::Version,
+    Address, Amount, CompressedPublicKey, EcdsaSighashType, Network, OutPoint, PrivateKey,
+    PublicKey, Script, ScriptBuf, Sequence, Transaction, TxIn, TxOut, Witness,
+};
+
+use chacha20poly1305::{
+    aead::{Aead, KeyInit},
+    ChaCha20Poly1305, Key, Nonce,
+};
+use dirs_next::data_dir;
+use rand::RngCore;
+use reqwest::Client;
+use rpassword::prompt_password;
+use rust_decimal::{prelude::ToPrimitive, Decimal};
+use serde::{Deserialize, Serialize};
+use std::{error::Error, fs, path::PathBuf, str::FromStr};
+
+mod coin_selection;
+
+/// Number of consecutive unused addresses to probe before stopping BIP44-style discovery.
+const GAP_LIMIT: u32 = 20;
+
+/// Byte lengths of the header fields prepended to the ChaCha20-Poly1305
+/// ciphertext in the on-disk mnemonic file: `salt || nonce || ciphertext`.
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+
+/// An address derived from the wallet's account key, along with the chain
+/// (0 = external/receive, 1 = internal/change) and index it was derived at.
+#[derive(Debug, Clone)]
+struct DerivedAddress {
+    chain: u32,
+    index: u32,
+    address: Address,
+}
+
+#[derive(Debug)]
+pub struct Wallet {
+    network: Network,
+    /// Present when the wallet was derived from a mnemonic; enables HD address
+    /// discovery. Absent for wallets constructed directly from a single WIF key.
+    account_xpriv: Option<Xpriv>,
+    address: Address,
+    wif_key: String,
+    /// API base URL for `network`, resolved from config once at construction
+    /// so per-request calls don't re-read the config file from disk.
+    api_url: String,
+    /// Price oracle endpoint, resolved from config once at construction.
+    price_api_url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct BalanceResponse {
+    #[serde(rename = "chain_stats")]
+    chain_stats: ChainStats,
+    #[serde(rename = "mempool_stats")]
+    mempool_stats: ChainStats,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChainStats {
+    funded_txo_sum: u64,
+    spent_txo_sum: u64,
+    tx_count: u32,
+}
+
+type UtxoResponse = Vec<Utxo>;
+
+#[derive(Debug, Clone, Deserialize)]
+struct Utxo {
+    txid: String,
+    vout: u32,
+    value: u64,
+    status: UtxoStatus,
+    /// Derivation chain (0 = external, 1 = internal) the owning address belongs
+    /// to. Not present in the API response; filled in after fetching.
+    #[serde(skip)]
+    chain: u32,
+    /// Derivation index of the owning address. Not present in the API response;
+    /// filled in after fetching.
+    #[serde(skip)]
+    index: u32,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct UtxoStatus {
+    confirmed: bool,
+    block_height: Option<u32>,
+    block_hash: Option<String>,
+    block_time: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Prevout {
+    scriptpubkey_address: Option<String>,
+    value: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct TxVin {
+    prevout: Option<Prevout>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TxVout {
+    scriptpubkey_address: Option<String>,
+    value: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct MempoolTx {
+    txid: String,
+    vin: Vec<TxVin>,
+    vout: Vec<TxVout>,
+    fee: u64,
+    status: UtxoStatus,
+}
+
+/// A single transaction's effect on the wallet, as reported by `get_history`.
+#[derive(Debug, Serialize)]
+pub struct HistoryEntry {
+    pub txid: String,
+    pub confirmed: bool,
+    pub block_height: Option<u32>,
+    /// Net satoshis received by our addresses minus sent from our addresses.
+    pub net_sats: i64,
+    pub fee: u64,
+}
+
+struct Fee {
+    low: u32,
+    medium: u32,
+    high: u32,
+}
+
+/// Fee tier selection for `send`, mapped onto the mempool.space recommended
+/// fee rates (sat/vB).
+#[derive(Debug, Clone, Copy)]
+pub enum FeeTier {
+    Low,
+    Medium,
+    High,
+}
+
+/// Outputs below this value (sats) are uneconomical to spend; a change output
+/// that would fall under it is dropped and its value folded into the fee.
+const DUST_LIMIT: u64 = 546;
+
+/// vsize contribution of a single P2WPKH input (weight 41*4 + 108 = 272, so
+/// vsize = 272/4 = 68) and output (weight 31*4 = 124, so vsize = 31), used by
+/// coin selection to estimate the per-input fee and the cost of change.
+const P2WPKH_INPUT_VSIZE: u64 = 68;
+const P2WPKH_OUTPUT_VSIZE: u64 = 31;
+
+#[derive(Debug, Deserialize)]
+struct MempoolFeeResponse {
+    #[serde(rename = "fastestFee")]
+    fastest_fee: u32,
+    #[serde(rename = "halfHourFee")]
+    half_hour_fee: u32,
+    #[serde(rename = "hourFee")]
+    hour_fee: u32,
+    #[serde(rename = "minimumFee")]
+    minimum_fee: u32,
+    #[serde(rename = "economyFee")]
+    economy_fee: u32,
+}
+
+/// Number of satoshis in one BTC, used to convert between sats and fiat.
+const SATS_PER_BTC: u64 = 100_000_000;
+
+#[derive(Debug, Deserialize)]
+struct PriceResponse {
+    #[serde(rename = "USD")]
+    usd: Decimal,
+}
+
+impl Wallet {
+    pub fn create(config: &Config, network: Network, passphrase: &str) -> Self {
+        let mut entropy: [u8; 16] = [0u8; 16];
+        rand::thread_rng().fill_bytes(&mut entropy);
+
+        let mnemonic = Mnemonic::from_entropy(&entropy).expect("Failed to generate mnemonic");
+
+        let wallet = Self::from_mnemonic(config, &mnemonic.to_string(), network);
+        Self::save_mnemonic(&mnemonic.to_string(), passphrase);
+        wallet
+    }
+
+    pub fn from_private_key(config: &Config, key: &str, network: Network) -> Self {
+        let secret_key =
+            SecretKey::from_str(key).expect("Failed to parse private key to secret key");
+        let private_key = PrivateKey::new(secret_key, network);
+
+        //get the public key
+        let secp = Secp256k1::new();
+        let compressed_public_key = CompressedPublicKey::from_private_key(&secp, &private_key)
+            .expect("Failed to create compressed public key");
+        let address = Address::p2wpkh(&compressed_public_key, network);
+
+        Self {
+            network,
+            account_xpriv: None,
+            address,
+            wif_key: key.to_string(),
+            api_url: config.api_url(network),
+            price_api_url: config.price_api_url(),
+        }
+    }
+
+    pub fn from_mnemonic(config: &Config, mnemonic_phrase: &str, network: Network) -> Self {
+        //parse the mnemonic phrase
+        let mnemonic = Mnemonic::parse_in(Language::English, mnemonic_phrase)
+            .expect("Failed to parse mnemonic");
+
+        //get the seed
+        let seed = mnemonic.to_seed("");
+
+        // Use the seed to derive an extended private key (BIP32 root key)
+        let secp = Secp256k1::new();
+        let master_xpriv =
+            Xpriv::new_master(network, &seed).expect("Failed to create extended private key");
+
+        // Derive the BIP84 account key (m/84'/0'/0'); receive/change addresses are
+        // then derived from this as m/0/i and m/1/i respectively.
+        let account_path = "m/84'/0'/0'"
+            .parse::<DerivationPath>()
+            .expect("Invalid derivation path");
+        let account_xpriv = master_xpriv
+            .derive_priv(&secp, &account_path)
+            .expect("Failed to derive account key");
+
+        let (_, address) = Self::derive_at(&account_xpriv, network, 0, 0);
+
+        Self {
+            network,
+            account_xpriv: Some(account_xpriv),
+            address,
+            wif_key: String::new(),
+            api_url: config.api_url(network),
+            price_api_url: config.price_api_url(),
+        }
+    }
+
+    /// Derives the private key and P2WPKH address at `m/<chain>/<index>` relative
+    /// to the given account key.
+    fn derive_at(
+        account_xpriv: &Xpriv,
+        network: Network,
+        chain: u32,
+        index: u32,
+    ) -> (SecretKey, Address) {
+        let secp = Secp256k1::new();
+        let path = DerivationPath::from_str(&format!("m/{}/{}", chain, index))
+            .expect("Invalid derivation path");
+        let child = account_xpriv
+            .derive_priv(&secp, &path)
+            .expect("Failed to derive child key");
+
+        let private_key = PrivateKey::new(child.private_key, network);
+        let compressed_public_key = CompressedPublicKey::from_private_key(&secp, &private_key)
+            .expect("Failed to create compressed public key");
+        let address = Address::p2wpkh(&compressed_public_key, network);
+
+        (child.private_key, address)
+    }
+
+    /// Same as `derive_at`, but relative to this wallet's own account key.
+    /// Returns `None` for wallets without HD derivation (e.g. bare private keys).
+    fn derive_child(&self, chain: u32, index: u32) -> Option<(SecretKey, Address)> {
+        let account_xpriv = self.account_xpriv?;
+        Some(Self::derive_at(&account_xpriv, self.network, chain, index))
+    }
+
+    fn get_storage_path() -> PathBuf {
+        // Get the OS-specific data directory and append your app's name
+        let mut path = data_dir().expect("Could not find data directory");
+        path.push("bitcli");
+        fs::create_dir_all(&path).expect("Failed to create app data directory");
+        path
+    }
+
+    /// Derives a 32-byte ChaCha20-Poly1305 key from `passphrase` and `salt`
+    /// using Argon2id.
+    fn derive_key(passphrase: &str, salt: &[u8]) -> [u8; 32] {
+        let mut key = [0u8; 32];
+        Argon2::default()
+            .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+            .expect("Failed to derive encryption key");
+        key
+    }
+
+    /// Encrypts `mnemonic` under `passphrase` and returns `salt || nonce ||
+    /// ciphertext` (the AEAD tag is appended to the ciphertext by the cipher).
+    fn encrypt_mnemonic(mnemonic: &str, passphrase: &str) -> Vec<u8> {
+        let mut salt = [0u8; SALT_LEN];
+        rand::thread_rng().fill_bytes(&mut salt);
+        let mut nonce_bytes = [0u8; NONCE_LEN];
+        rand::thread_rng().fill_bytes(&mut nonce_bytes);
+
+        let key = Self::derive_key(passphrase, &salt);
+        let cipher = ChaCha20Poly1305::new(Key::from_slice(&key));
+        let ciphertext = cipher
+            .encrypt(Nonce::from_slice(&nonce_bytes), mnemonic.as_bytes())
+            .expect("Failed to encrypt mnemonic");
+
+        let mut out = Vec::with_capacity(SALT_LEN + NONCE_LEN + ciphertext.len());
+        out.extend_from_slice(&salt);
+        out.extend_from_slice(&nonce_bytes);
+        out.extend_from_slice(&ciphertext);
+        out
+    }
+
+    /// Decrypts a `salt || nonce || ciphertext` blob produced by
+    /// `encrypt_mnemonic`, returning an error on a bad passphrase or tag
+    /// mismatch rather than silently producing garbage.
+    fn decrypt_mnemonic(data: &[u8], passphrase: &str) -> Result<String, Box<dyn Error>> {
+        if data.len() < SALT_LEN + NONCE_LEN {
+            return Err("Corrupt wallet file".into());
+        }
+
+        let (salt, rest) = data.split_at(SALT_LEN);
+        let (nonce_bytes, ciphertext) = rest.split_at(NONCE_LEN);
+
+        let key = Self::derive_key(passphrase, salt);
+        let cipher = ChaCha20Poly1305::new(Key::from_slice(&key));
+        let plaintext = cipher
+            .decrypt(Nonce::from_slice(nonce_bytes), ciphertext)
+            .map_err(|_| "Incorrect passphrase or corrupted wallet file")?;
+
+        Ok(String::from_utf8(plaintext)?)
+    }
+
+    /// Old versions of bitcli wrote the mnemonic to disk as plaintext. Detects
+    /// that legacy format so it can be transparently migrated on first unlock.
+    fn is_legacy_plaintext(data: &[u8]) -> bool {
+        match std::str::from_utf8(data) {
+            Ok(text) => {
+                text.split_whitespace().count() >= 12
+                    && text
+                        .chars()
+                        .all(|c| c.is_ascii_lowercase() || c.is_whitespace())
+            }
+            Err(_) => false,
+        }
+    }
+
+    pub fn save_mnemonic(mnemonic: &str, passphrase: &str) {
+        let storage_path = Self::get_storage_path();
+        let file_path = storage_path.join("mnemonic.txt");
+        let encrypted = Self::encrypt_mnemonic(mnemonic, passphrase);
+        fs::write(file_path, encrypted).expect("Failed to save mnemonic");
+    }
+
+    pub fn load_mnemonic() -> String {
+        let storage_path = Self::get_storage_path();
+        let file_path = storage_path.join("mnemonic.txt");
+        let data = match fs::read(file_path) {
+            Ok(data) => data,
+            Err(_) => return String::new(),
+        };
+
+        if data.is_empty() {
+            return String::new();
+        }
+
+        if Self::is_legacy_plaintext(&data) {
+            let mnemonic = String::from_utf8(data).expect("Failed to read legacy mnemonic file");
+            println!("Found an unencrypted wallet file; choose a passphrase to encrypt it.");
+            let passphrase =
+                prompt_password("New passphrase: ").expect("Failed to read passphrase");
+            Self::save_mnemonic(&mnemonic, &passphrase);
+            return mnemonic;
+        }
+
+        let passphrase = prompt_password("Wallet passphrase: ").expect("Failed to read passphrase");
+        match Self::decrypt_mnemonic(&data, &passphrase) {
+            Ok(mnemonic) => mnemonic,
+            Err(e) => {
+                eprintln!("Failed to unlock wallet: {}", e);
+                String::new()
+            }
+        }
+    }
+
+    /// Returns a fresh, unused receive address, discovering past usage first so
+    /// we don't hand out an address that's already been used.
+    pub async fn get_address(&self) -> Result<String, Box<dyn Error>> {
+        let account_xpriv = match self.account_xpriv {
+            Some(account_xpriv) => account_xpriv,
+            None => return Ok(self.address.to_string()),
+        };
+
+        let mut index = 0u32;
+        loop {
+            let (_, address) = Self::derive_at(&account_xpriv, self.network, 0, index);
+            if !self.is_address_active(&address).await? {
+                return Ok(address.to_string());
+            }
+            index += 1;
+        }
+    }
+
+    /// Returns a fresh, unused internal/change address, mirroring
+    /// `get_address`'s discovery of chain 0. Wallets without HD derivation
+    /// have no separate change chain, so they reuse their single address.
+    async fn get_change_address(&self) -> Result<Address, Box<dyn Error>> {
+        let account_xpriv = match self.account_xpriv {
+            Some(account_xpriv) => account_xpriv,
+            None => return Ok(self.address.clone()),
+        };
+
+        let mut index = 0u32;
+        loop {
+            let (_, address) = Self::derive_at(&account_xpriv, self.network, 1, index);
+            if !self.is_address_active(&address).await? {
+                return Ok(address);
+            }
+            index += 1;
+        }
+    }
+
+    async fn is_address_active(&self, address: &Address) -> Result<bool, Box<dyn Error>> {
+        let api_url = self.api_url.clone();
+        if api_url.is_empty() {
+            return Err("Invalid network".into());
+        }
+
+        let url = format!("{}/api/address/{}", api_url, address);
+        let response = reqwest::get(url).await?;
+        let data: BalanceResponse = response.json().await?;
+
+        Ok(data.chain_stats.tx_count > 0 || data.mempool_stats.tx_count > 0)
+    }
+
+    /// Scans sequential indices on `chain`, stopping after `GAP_LIMIT`
+    /// consecutive addresses with no on-chain or mempool activity.
+    async fn discover_chain(&self, chain: u32) -> Result<Vec<DerivedAddress>, Box<dyn Error>> {
+        let mut discovered = Vec::new();
+        let mut index = 0u32;
+        let mut unused_run = 0u32;
+
+        while unused_run < GAP_LIMIT {
+            let (_, address) = self
+                .derive_child(chain, index)
+                .expect("discover_chain requires an HD wallet");
+
+            if self.is_address_active(&address).await? {
+                discovered.push(DerivedAddress {
+                    chain,
+                    index,
+                    address,
+                });
+                unused_run = 0;
+            } else {
+                unused_run += 1;
+            }
+
+            index += 1;
+        }
+
+        Ok(discovered)
+    }
+
+    /// Discovers every address with activity across the external and internal
+    /// chains using gap-limit scanning. Wallets without HD derivation just
+    /// return their single address.
+    async fn discover_addresses(&self) -> Result<Vec<DerivedAddress>, Box<dyn Error>> {
+        if self.account_xpriv.is_none() {
+            return Ok(vec![DerivedAddress {
+                chain: 0,
+                index: 0,
+                address: self.address.clone(),
+            }]);
+        }
+
+        let mut addresses = self.discover_chain(0).await?;
+        addresses.extend(self.discover_chain(1).await?);
+        Ok(addresses)
+    }
+
+    pub async fn get_balance(&self) -> Result<u64, Box<dyn Error>> {
+        let api_url = self.api_url.clone();
+        if api_url.is_empty() {
+            return Err("Invalid network".into());
+        }
+
+        let addresses = self.discover_addresses().await?;
+        let mut balance: u64 = 0;
+
+        for derived in &addresses {
+            let url = format!("{}/api/address/{}", api_url, derived.address);
+            let response = reqwest::get(url).await?;
+            let data: BalanceResponse = response.json().await?;
+            balance += data.chain_stats.funded_txo_sum - data.chain_stats.spent_txo_sum;
+        }
+
+        Ok(balance)
+    }
+
+    pub fn get_network(&self) -> String {
+        self.network.to_string()
+    }
+
+    /// Deletes all stored wallet data. Doesn't require the mnemonic to be
+    /// unlocked first, so it stays usable even if the passphrase is lost.
+    pub fn reset() {
+        let storage_path = Self::get_storage_path();
+        fs::remove_dir_all(storage_path).expect("Failed to reset");
+    }
+
+    async fn fetch_utxos(&self) -> Result<UtxoResponse, Box<dyn Error>> {
+        let api_url = self.api_url.clone();
+        if api_url.is_empty() {
+            return Err("Invalid network".into());
+        }
+
+        let addresses = self.discover_addresses().await?;
+        let mut utxos = Vec::new();
+
+        for derived in &addresses {
+            let url = format!("{}/api/address/{}/utxo", api_url, derived.address);
+            let response = reqwest::get(url).await?;
+            let mut data: UtxoResponse = response.json().await.expect("Failed to parse utxos");
+
+            for utxo in &mut data {
+                utxo.chain = derived.chain;
+                utxo.index = derived.index;
+            }
+
+            utxos.extend(data);
+        }
+
+        Ok(utxos)
+    }
+
+    /// Fetches the transaction history for every discovered address, paging
+    /// through the `/txs/chain/{last_txid}` cursor, and summarizes each
+    /// transaction's net effect on the wallet. A transaction touching more
+    /// than one of our addresses is only reported once.
+    pub async fn get_history(&self) -> Result<Vec<HistoryEntry>, Box<dyn Error>> {
+        let api_url = self.api_url.clone();
+        if api_url.is_empty() {
+            return Err("Invalid network".into());
+        }
+
+        let addresses = self.discover_addresses().await?;
+        let our_addresses: std::collections::HashSet<String> = addresses
+            .iter()
+            .map(|derived| derived.address.to_string())
+            .collect();
+
+        let mut seen: std::collections::HashMap<String, MempoolTx> =
+            std::collections::HashMap::new();
+
+        for derived in &addresses {
+            let address = derived.address.to_string();
+            let mut last_txid: Option<String> = None;
+
+            loop {
+                let url = match &last_txid {
+                    Some(txid) => format!("{}/api/address/{}/txs/chain/{}", api_url, address, txid),
+                    None => format!("{}/api/address/{}/txs", api_url, address),
+                };
+
+                let page: Vec<MempoolTx> = reqwest::get(url).await?.json().await?;
+                if page.is_empty() {
+                    break;
+                }
+
+                last_txid = page.last().map(|tx| tx.txid.clone());
+
+                for tx in page {
+                    seen.entry(tx.txid.clone()).or_insert(tx);
+                }
+            }
+        }
+
+        let mut history: Vec<HistoryEntry> = seen
+            .into_values()
+            .map(|tx| {
+                let received: i64 = tx
+                    .vout
+                    .iter()
+                    .filter(|out| {
+                        out.scriptpubkey_address
+                            .as_deref()
+                            .is_some_and(|a| our_addresses.contains(a))
+                    })
+                    .map(|out| out.value as i64)
+                    .sum();
+
+                let sent: i64 = tx
+                    .vin
+                    .iter()
+                    .filter_map(|input| input.prevout.as_ref())
+                    .filter(|prevout| {
+                        prevout
+                            .scriptpubkey_address
+                            .as_deref()
+                            .is_some_and(|a| our_addresses.contains(a))
+                    })
+                    .map(|prevout| prevout.value as i64)
+                    .sum();
+
+                HistoryEntry {
+                    txid: tx.txid,
+                    confirmed: tx.status.confirmed,
+                    block_height: tx.status.block_height,
+                    net_sats: received - sent,
+                    fee: tx.fee,
+                }
+            })
+            .collect();
+
+        history.sort_by(|a, b| {
+            b.block_height
+                .unwrap_or(u32::MAX)
+                .cmp(&a.block_height.unwrap_or(u32::MAX))
+        });
+
+        Ok(history)
+    }
+
+    async fn fetch_fee_rates(&self) -> Result<Fee, Box<dyn Error>> {
+        let api_url = self.api_url.clone();
+        if api_url.is_empty() {
+            return Err("Invalid network".into());
+        }
+
+        let url = format!("{}/api/v1/fees/recommended", api_url);
+        let response = reqwest::get(url).await?;
+        let data: MempoolFeeResponse = response.json().await?;
+
+        Ok(Fee {
+            low: data.minimum_fee,
+            medium: data.half_hour_fee,
+            high: data.fastest_fee,
+        })
+    }
+
+    /// Fetches the current BTC/USD spot price from the price oracle.
+    pub async fn get_btc_price(&self) -> Result<Decimal, Box<dyn Error>> {
+        let response = reqwest::get(self.price_api_url.clone()).await?;
+        let data: PriceResponse = response.json().await?;
+
+        Ok(data.usd)
+    }
+
+    /// Converts a satoshi amount to its fiat value at `price_per_btc`, using
+    /// decimal rather than float arithmetic to avoid rounding errors. The
+    /// division and multiplication are unchecked and panic on overflow.
+    pub fn sats_to_fiat(sats: u64, price_per_btc: Decimal) -> Decimal {
+        Decimal::from(sats) / Decimal::from(SATS_PER_BTC) * price_per_btc
+    }
+
+    /// Converts a fiat amount to satoshis at `price_per_btc`, rounding down to
+    /// the nearest whole satoshi. Returns `None` if `price_per_btc` is zero.
+    pub fn fiat_to_sats(fiat: Decimal, price_per_btc: Decimal) -> Option<u64> {
+        if price_per_btc.is_zero() {
+            return None;
+        }
+
+        (fiat / price_per_btc * Decimal::from(SATS_PER_BTC))
+            .trunc()
+            .to_u64()
+    }
+
+    /// Estimates the virtual size (vbytes) of a P2WPKH transaction with the
+    /// given input/output count, per BIP141 weight units: each input is 41
+    /// non-witness bytes plus ~108 witness bytes, each output is 31 bytes, with
+    /// 10 bytes of fixed overhead plus the 2-byte segwit marker/flag.
+    fn estimate_vsize(&self, inputs: u32, outputs: u32) -> u32 {
+        let base_bytes = 10 + (inputs * 41) + (outputs * 31);
+        let witness_bytes = 2 + (inputs * 108);
+        let weight = base_bytes * 4 + witness_bytes;
+
+        (weight + 3) / 4
+    }
+
+    fn sign_tx(
+        &self,
+        mut tx: Transaction,
+        utxos: &Vec<Utxo>,
+    ) -> Result<Transaction, Box<dyn Error>> {
+        let secp = Secp256k1::new();
+        let mut sighasher = SighashCache::new(&mut tx);
+
+        // Sign each input with the key for the address it actually belongs to.
+        for (index, utxo) in utxos.iter().enumerate() {
+            let (secret_key, script_pubkey) = match self.derive_child(utxo.chain, utxo.index) {
+                Some((secret_key, address)) => (secret_key, address.script_pubkey()),
+                None => (
+                    SecretKey::from_str(&self.wif_key)?,
+                    self.address.script_pubkey(),
+                ),
+            };
+
+            let sighash_type = EcdsaSighashType::All;
+            let amount = Amount::from_sat(utxo.value);
+            let sighash = sighasher.p2wpkh_signature_hash(
+                index,
+                script_pubkey.as_script(),
+                amount,
+                sighash_type,
+            )?;
+
+            // Sign sighash
+            let sighash_bytes: &[u8] = &sighash[..];
+            let message = Message::from_digest_slice(&sighash_bytes).unwrap();
+            let signature = secp.sign_ecdsa(&message, &secret_key);
+
+            // Convert signature to Bitcoin-specific format
+            let mut sig_with_hashtype = signature.serialize_der().to_vec();
+            sig_with_hashtype.push(sighash_type as u8);
+
+            // Add public key for verification
+            let public_key = secret_key.public_key(&secp);
+            let public_key_bytes = public_key.serialize().to_vec();
+
+            // Update witness
+            sighasher
+                .witness_mut(index)
+                .unwrap()
+                .push(sig_with_hashtype);
+            sighasher.witness_mut(index).unwrap().push(public_key_bytes);
+        }
+
+        Ok(sighasher.into_transaction().clone())
+    }
+
+    /// Builds a transaction paying every `(address, amount)` recipient plus a
+    /// single change output unless the change would be dust, selecting inputs from `utxos` via
+    /// Branch-and-Bound coin selection rather than spending all of them.
+    /// Fails the whole batch if funds are insufficient for all recipients
+    /// together. `sat_per_vb_override` takes precedence over `fee_tier` when
+    /// set.
+    async fn build_tx(
+        &self,
+        recipients: &[(String, u64)],
+        utxos: &Vec<Utxo>,
+        fee_tier: FeeTier,
+        sat_per_vb_override: Option<u64>,
+    ) -> Result<(Transaction, Vec<Utxo>), Box<dyn Error>> {
+        let sat_per_vb = match sat_per_vb_override {
+            Some(rate) => rate as u32,
+            None => {
+                let fee_rate = self
+                    .fetch_fee_rates()
+                    .await
+                    .expect("Failed to fetch fee rates");
+                match fee_tier {
+                    FeeTier::Low => fee_rate.low,
+                    FeeTier::Medium => fee_rate.medium,
+                    FeeTier::High => fee_rate.high,
+                }
+            }
+        };
+
+        let total_send: u64 = recipients.iter().map(|(_, amount)| amount).sum();
+
+        // Fixed overhead plus the recipient outputs, before any inputs are chosen.
+        let base_fee = self.estimate_vsize(0, recipients.len() as u32) as u64 * sat_per_vb as u64;
+        let fee_per_input = P2WPKH_INPUT_VSIZE * sat_per_vb as u64;
+        let cost_of_change = (P2WPKH_OUTPUT_VSIZE + P2WPKH_INPUT_VSIZE) * sat_per_vb as u64;
+        let target = total_send + base_fee;
+
+        let selected = coin_selection::select_coins(utxos, target, fee_per_input, cost_of_change)
+            .ok_or("Insufficient funds")?;
+
+        let total_selected: u64 = selected.iter().map(|utxo| utxo.value).sum();
+        let selected: Vec<Utxo> = selected.into_iter().cloned().collect();
+
+        let inputs: Vec<TxIn> = selected
+            .iter()
+            .map(|utxo| {
+                let txid = bitcoin::Txid::from_str(&utxo.txid).unwrap();
+
+                TxIn {
+                    previous_output: OutPoint {
+                        txid,
+                        vout: utxo.vout,
+                    },
+                    script_sig: ScriptBuf::default(),
+                    sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+                    witness: bitcoin::Witness::new(),
+                }
+            })
+            .collect();
+
+        let mut outputs: Vec<TxOut> = Vec::with_capacity(recipients.len() + 1);
+        for (to, amount) in recipients {
+            let recipient_address = Address::from_str(to)?.require_network(self.network)?;
+            outputs.push(TxOut {
+                value: Amount::from_sat(*amount),
+                script_pubkey: recipient_address.script_pubkey(),
+            });
+        }
+
+        // Recompute the fee now that the exact input count is known. Coin
+        // selection may have fallen back to `largest_first`, which has no
+        // bound on how much it overshoots `target`, so a change output must
+        // always be sized and only dropped below the dust limit, never
+        // skipped based on the no-change fee fitting.
+        let vsize_with_change =
+            self.estimate_vsize(inputs.len() as u32, recipients.len() as u32 + 1);
+        let fee_with_change = vsize_with_change as u64 * sat_per_vb as u64;
+        let fee_without_change = self.estimate_vsize(inputs.len() as u32, recipients.len() as u32)
+            as u64
+            * sat_per_vb as u64;
+
+        if total_send + fee_without_change > total_selected {
+            return Err("Insufficient funds".into());
+        }
+
+        // Change too small to pay for its own output comes out as 0 and is
+        // dropped below, leaving the surplus to the fee.
+        let change = (total_selected - total_send).saturating_sub(fee_with_change);
+
+        if change >= DUST_LIMIT {
+            let change_address = self.get_change_address().await?;
+            outputs.push(TxOut {
+                value: Amount::from_sat(change),
+                script_pubkey: change_address.script_pubkey(),
+            });
+        }
+
+        let tx = Transaction {
+            version: Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: inputs,
+            output: outputs,
+        };
+
+        Ok((tx, selected))
+    }
+
+    async fn broadcast(&self, tx: Transaction) -> Result<String, Box<dyn Error>> {
+        let api_url = self.api_url.clone();
+        if api_url.is_empty() {
+            return Err("Invalid network".into());
+        }
+
+        let client = Client::new();
+
+        let raw_tx = serialize(&tx);
+        let raw_tx_hex = hex::encode(raw_tx);
+
+        let url = format!("{}/api/tx", api_url);
+        let response = client
+            .post(url)
+            .header("Content-Type", "application/json")
+            .body(raw_tx_hex)
+            .send()
+            .await?;
+
+        if response.status().is_success() {
+            let txid = response.text().await?;
+            Ok(txid)
+        } else {
+            let error_message = response.text().await?;
+            Err(format!("Failed to broadcast transaction: {}", error_message).into())
+        }
+    }
+
+    pub async fn send(
+        &self,
+        recipients: &[(String, u64)],
+        fee_tier: FeeTier,
+        sat_per_vb_override: Option<u64>,
+    ) -> Result<String, Box<dyn Error>> {
+        let utxos: Vec<Utxo> = self.fetch_utxos().await?;
+        let (tx, selected_utxos) = self
+            .build_tx(recipients, &utxos, fee_tier, sat_per_vb_override)
+            .await?;
+        let signed_tx = self.sign_tx(tx, &selected_utxos)?;
+
+        let txid = self.broadcast(signed_tx).await?;
+
+        Ok(txid)
+    }
+
+    /// Builds the same unsigned transaction as `send`, but returns it as a
+    /// base64-encoded PSBT (BIP174) instead of broadcasting, so it can be
+    /// signed offline or by other participants in a multisig.
+    pub async fn build_psbt(
+        &self,
+        recipients: &[(String, u64)],
+        fee_tier: FeeTier,
+        sat_per_vb_override: Option<u64>,
+    ) -> Result<String, Box<dyn Error>> {
+        let utxos: Vec<Utxo> = self.fetch_utxos().await?;
+        let (tx, selected_utxos) = self
+            .build_tx(recipients, &utxos, fee_tier, sat_per_vb_override)
+            .await?;
+
+        let mut psbt = Psbt::from_unsigned_tx(tx)?;
+        let secp = Secp256k1::new();
+
+        for (input, utxo) in psbt.inputs.iter_mut().zip(selected_utxos.iter()) {
+            let (secret_key, address) = match self.derive_child(utxo.chain, utxo.index) {
+                Some(pair) => pair,
+                None => (SecretKey::from_str(&self.wif_key)?, self.address.clone()),
+            };
+
+            input.witness_utxo = Some(TxOut {
+                value: Amount::from_sat(utxo.value),
+                script_pubkey: address.script_pubkey(),
+            });
+
+            if let Some(account_xpriv) = self.account_xpriv {
+                let fingerprint = account_xpriv.fingerprint(&secp);
+                let path = DerivationPath::from_str(&format!("m/{}/{}", utxo.chain, utxo.index))?;
+                let public_key = secret_key.public_key(&secp);
+                input
+                    .bip32_derivation
+                    .insert(public_key, (fingerprint, path));
+            }
+        }
+
+        Ok(base64_standard.encode(psbt.serialize()))
+    }
+
+    /// Loads a base64-encoded PSBT and fills in signatures for any inputs our
+    /// derived keys control (matched via each input's BIP32 derivation path),
+    /// then re-exports it. Inputs we don't control are left untouched, so the
+    /// PSBT can accumulate signatures from other multisig participants.
+    pub fn sign_psbt(&self, psbt_base64: &str) -> Result<String, Box<dyn Error>> {
+        let bytes = base64_standard.decode(psbt_base64)?;
+        let mut psbt = Psbt::deserialize(&bytes)?;
+
+        let secp = Secp256k1::new();
+        let mut sighasher = SighashCache::new(&psbt.unsigned_tx);
+
+        for index in 0..psbt.inputs.len() {
+            let Some(utxo) = psbt.inputs[index].witness_utxo.clone() else {
+                continue;
+            };
+
+            let our_key =
+                psbt.inputs[index]
+                    .bip32_derivation
+                    .iter()
+                    .find_map(|(public_key, (_, path))| {
+                        let (chain, derivation_index) = Self::parse_chain_index(path)?;
+                        let (secret_key, address) = self.derive_child(chain, derivation_index)?;
+                        (address.script_pubkey() == utxo.script_pubkey)
+                            .then_some((secret_key, *public_key))
+                    });
+
+            let Some((secret_key, public_key)) = our_key else {
+                continue;
+            };
+
+            let sighash_type = EcdsaSighashType::All;
+            let sighash = sighasher.p2wpkh_signature_hash(
+                index,
+                &utxo.script_pubkey,
+                utxo.value,
+                sighash_type,
+            )?;
+
+            let message = Message::from_digest_slice(&sighash[..])?;
+            let signature = ecdsa::Signature {
+                signature: secp.sign_ecdsa(&message, &secret_key),
+                sighash_type,
+            };
+
+            psbt.inputs[index]
+                .partial_sigs
+                .insert(PublicKey::new(public_key), signature);
+        }
+
+        Ok(base64_standard.encode(psbt.serialize()))
+    }
+
+    /// Turns a `m/<chain>/<index>` derivation path back into its components.
+    fn parse_chain_index(path: &DerivationPath) -> Option<(u32, u32)> {
+        let components: Vec<ChildNumber> = path.into_iter().copied().collect();
+        if components.len() != 2 {
+            return None;
+        }
+
+        match (components[0], components[1]) {
+            (ChildNumber::Normal { index: chain }, ChildNumber::Normal { index }) => {
+                Some((chain, index))
+            }
+            _ => None,
+        }
+    }
+
+    /// Extracts the final transaction from a fully-signed PSBT and broadcasts
+    /// it, the same way `send` does for a locally-built transaction.
+    pub async fn finalize_psbt(&self, psbt_base64: &str) -> Result<String, Box<dyn Error>> {
+        let bytes = base64_standard.decode(psbt_base64)?;
+        let mut psbt = Psbt::deserialize(&bytes)?;
+
+        for input in psbt.inputs.iter_mut() {
+            let (public_key, signature) = input
+                .partial_sigs
+                .iter()
+                .next()
+                .map(|(public_key, signature)| (*public_key, *signature))
+                .ok_or("Missing signature for PSBT input")?;
+
+            let mut witness = Witness::new();
+            witness.push(signature.to_vec());
+            witness.push(public_key.to_bytes());
+
+            input.final_script_witness = Some(witness);
+            input.partial_sigs.clear();
+            input.bip32_derivation.clear();
+            input.witness_utxo = None;
+            input.sighash_type = None;
+        }
+
+        let tx = psbt.extract_tx()?;
+        let txid = self.broadcast(tx).await?;
+
+        Ok(txid)
+    }
+}